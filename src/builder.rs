@@ -1,6 +1,6 @@
 //! Construct a radio transmission struct with calls to a builder helper.
 
-use crate::{Transmission, ProtocolProperties};
+use crate::{Transmission, ProtocolProperties, TimingMode};
 
 #[derive(Default)]
 /// Creates an instance of a transmission struct.
@@ -21,6 +21,7 @@ pub struct TransmissionBuilder {
     pulse_length: u16,
     repeats: u8,
     protocol: ProtocolProperties,
+    timing: TimingMode,
 }
 
 impl TransmissionBuilder {
@@ -31,7 +32,8 @@ impl TransmissionBuilder {
             sequence: String::from(""),
             pulse_length: 0,
             repeats: 0,
-            protocol: ProtocolProperties::default()
+            protocol: ProtocolProperties::default(),
+            timing: TimingMode::default()
         }
     }
 
@@ -75,13 +77,22 @@ impl TransmissionBuilder {
         self
     }
 
+    /// Specify how the pulse durations are timed. Defaults to
+    /// [`TimingMode::Sleep`]; switch to [`TimingMode::BusyWait`] for
+    /// accurate short pulses on a non-realtime kernel.
+    pub fn timing(mut self, timing: TimingMode) -> TransmissionBuilder {
+        self.timing = timing;
+        self
+    }
+
     /// Finalizes the build and creates a `Transmission` struct.
     pub fn build(&self) -> Transmission {
         Transmission {
             sequence: self.sequence.clone(),
             pulse_length: self.pulse_length,
             repeats: self.repeats,
-            protocol: self.protocol
+            protocol: self.protocol,
+            timing: self.timing
         }
     }
 
@@ -108,6 +119,7 @@ pub struct ProtocolBuilder {
     long: u8,
     sync_bit: u8,
     sync_gap: u8,
+    inverted: bool,
 }
 
 impl ProtocolBuilder {
@@ -146,13 +158,22 @@ impl ProtocolBuilder {
         self
     }
 
+    /// Mark the protocol as using an inverted signal, i.e. the line is
+    /// driven low during the pulse and high during the gap. Most
+    /// protocols leave this `false`.
+    pub fn inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+
     /// Creates a new instance of `ProtocolProperties`
     pub fn build(&self) -> ProtocolProperties {
         ProtocolProperties {
             short: self.short,
             long: self.long,
             sync_bit: self.sync_bit,
-            sync_gap: self.sync_gap
+            sync_gap: self.sync_gap,
+            inverted: self.inverted
         }
     }
 
@@ -0,0 +1,106 @@
+//! Build the binary `sequence` for common remote-control socket
+//! families, so callers don't have to hand-craft raw '0'/'1' strings.
+//!
+//! The functions mirror the switch types from sui77's
+//! [rc-switch library](https://github.com/sui77/rc-switch) and the
+//! [funksteckdose](https://crates.io/crates/funksteckdose) crate. Each
+//! one returns a [`String`] that can be dropped straight into
+//! [`TransmissionBuilder::sequence`](crate::builder::TransmissionBuilder::sequence).
+//!
+//! Both families are transmitted with protocol [`P1`](crate::P1).
+
+/// Expand a string of tristate values into the two-bit rc-switch
+/// symbols: `'0'` becomes `"00"`, `'1'` becomes `"11"` and the floating
+/// `'f'` becomes `"01"` (upper case `'F'` is treated the same).
+fn expand_tristate(tristate: &str) -> String {
+    let mut out = String::with_capacity(tristate.len() * 2);
+    for c in tristate.chars() {
+        match c {
+            '0' => out.push_str("00"),
+            '1' => out.push_str("11"),
+            'f' | 'F' => out.push_str("01"),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Build the sequence for a Type A switch, i.e. the ubiquitous remotes
+/// with two banks of five DIP switches (10-pole, "system code" and
+/// "unit code").
+///
+/// `group` and `device` are five-character tristate codes made up of
+/// `'0'`, `'1'` and floating `'f'` values that mirror the physical DIP
+/// switch positions. The `on` flag selects the on (`"0F"`) or off
+/// (`"F0"`) command. A trailing sync marker is appended so the signal
+/// can be handed straight to a builder.
+///
+/// # Examples
+/// ```rust
+/// use libsparkypi::*;
+///
+/// let signal = Transmission::builder()
+///     .sequence(&encoding::type_a("11011", "10000", true))
+///     .pulse_length(320)
+///     .repeats(10)
+///     .protocol(P1)
+///     .build();
+/// ```
+pub fn type_a(group: &str, device: &str, on: bool) -> String {
+    let mut tristate = String::with_capacity(12);
+    tristate.push_str(group);
+    tristate.push_str(device);
+    tristate.push_str(if on { "0F" } else { "F0" });
+
+    format!("{}s", expand_tristate(&tristate))
+}
+
+/// Build the sequence for a Type C (Intertechno) switch.
+///
+/// `house` is the house letter `'a'`–`'p'`, `group` and `device` are in
+/// the range `1..=4`, and `on` selects the command. The address bits are
+/// laid out the same way as rc-switch's `getCodeWordC`. A trailing sync
+/// marker is appended.
+///
+/// Returns an empty string if any argument is out of range.
+///
+/// # Examples
+/// ```rust
+/// use libsparkypi::*;
+///
+/// let signal = Transmission::builder()
+///     .sequence(&encoding::type_c('a', 1, 2, false))
+///     .pulse_length(320)
+///     .repeats(10)
+///     .protocol(P1)
+///     .build();
+/// ```
+pub fn type_c(house: char, group: u8, device: u8, on: bool) -> String {
+    let family = match house.to_ascii_lowercase() {
+        c @ 'a'..='p' => c as u8 - b'a',
+        _ => return String::new(),
+    };
+    if !(1..=4).contains(&group) || !(1..=4).contains(&device) {
+        return String::new();
+    }
+
+    let mut tristate = String::with_capacity(12);
+    // Family, least significant bit first.
+    for i in 0..4 {
+        tristate.push(if family & (1 << i) != 0 { 'F' } else { '0' });
+    }
+    // Device then group, each two bits, least significant bit first.
+    for i in 0..2 {
+        tristate.push(if (device - 1) & (1 << i) != 0 { 'F' } else { '0' });
+    }
+    for i in 0..2 {
+        tristate.push(if (group - 1) & (1 << i) != 0 { 'F' } else { '0' });
+    }
+    // Fixed status preamble and the on/off bit.
+    tristate.push('0');
+    tristate.push('F');
+    tristate.push('F');
+    tristate.push(if on { 'F' } else { '0' });
+
+    format!("{}s", expand_tristate(&tristate))
+}
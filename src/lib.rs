@@ -2,10 +2,19 @@
 //! mains socket switches, doorbells and similar radio controlled
 //! devices.
 
+use embedded_hal::digital::OutputPin;
+use embedded_hal::delay::DelayNs;
+
+#[cfg(feature = "gpio-cdev")]
 use std::{thread, time};
+#[cfg(feature = "gpio-cdev")]
 use gpio_cdev::{Chip, LineRequestFlags, LineHandle};
 
 pub mod builder;
+pub mod encoding;
+pub mod packet;
+#[cfg(feature = "gpio-cdev")]
+pub mod receiver;
 use crate::builder::{TransmissionBuilder, ProtocolBuilder};
 
 /// A transmission consists of a sequence of short and long radio pulses.
@@ -28,23 +37,47 @@ pub struct Transmission {
     pub pulse_length: u16,
     pub repeats: u8,
     pub protocol: ProtocolProperties,
+    pub timing: TimingMode,
+}
+
+/// Selects how the pulse durations are timed during transmission.
+///
+/// `thread::sleep` is simple but the OS scheduler and syscall overhead
+/// make sub-millisecond sleeps wildly inaccurate on a non-realtime
+/// kernel, so short pulses get stretched and many sockets fail to
+/// trigger. `BusyWait` trades CPU for accuracy by spinning on
+/// [`Instant::now`](std::time::Instant::now) until the target elapses.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum TimingMode {
+    /// Sleep the thread for each pulse. Low CPU, low accuracy.
+    #[default]
+    Sleep,
+    /// Busy-spin until each pulse duration has elapsed. High CPU, high
+    /// accuracy - the right choice on a Raspberry Pi.
+    BusyWait,
 }
 
 /// In the protocol we define the smallest parts of the radio signal.
 /// Usually a short pulse with a long pause resembles a binary zero,
 /// and a long pulse followed by a short pause resembles a binary one.
 /// A sync bit / sync gap combination marks the beginning of the radio transmission.
+///
+/// Some remotes invert this convention and drive the line low during
+/// the pulse and high during the gap. Set `inverted` to `true` for
+/// those, mirroring the `invertedSignal` flag of the rc-switch protocol
+/// table.
 #[derive(Debug, Default, Copy, Clone, PartialEq)]
 pub struct ProtocolProperties {
     pub short: u8,
     pub long: u8,
     pub sync_bit: u8,
     pub sync_gap: u8,
+    pub inverted: bool,
 }
 
 impl ProtocolProperties {
-    
-    /// Initialize a custom protocol. Every field is set to zero.    
+
+    /// Initialize a custom protocol. Every field is set to zero.
     pub fn new() -> Self {
         Default::default()
     }
@@ -53,6 +86,34 @@ impl ProtocolProperties {
     pub fn builder() -> ProtocolBuilder {
         ProtocolBuilder::default()
     }
+
+    /// Look a protocol up by its rc-switch number, from `1` to `12`.
+    /// Returns `None` for any other index.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libsparkypi::*;
+    ///
+    /// assert_eq!(ProtocolProperties::from_index(1), Some(P1));
+    /// assert_eq!(ProtocolProperties::from_index(0), None);
+    /// ```
+    pub fn from_index(index: u8) -> Option<ProtocolProperties> {
+        match index {
+            1 => Some(P1),
+            2 => Some(P2),
+            3 => Some(P3),
+            4 => Some(P4),
+            5 => Some(P5),
+            6 => Some(P6),
+            7 => Some(P7),
+            8 => Some(P8),
+            9 => Some(P9),
+            10 => Some(P10),
+            11 => Some(P11),
+            12 => Some(P12),
+            _ => None,
+        }
+    }
 }
 
 /// Resembles 'protocol1' of sui77's brilliant
@@ -63,6 +124,7 @@ pub const P1: ProtocolProperties = ProtocolProperties {
     long: 3,
     sync_bit: 1,
     sync_gap: 31,
+    inverted: false,
 };
 
 /// Resembles 'protocol2'.
@@ -72,6 +134,7 @@ pub const P2: ProtocolProperties = ProtocolProperties {
     long: 2,
     sync_bit: 1,
     sync_gap: 10,
+    inverted: false,
 };
 
 /// Protocol for the 'Gmornxen' RC socket switches, very similar to 'protocol2'.
@@ -80,6 +143,101 @@ pub const XEN: ProtocolProperties = ProtocolProperties {
     long: 2,
     sync_bit: 1,
     sync_gap: 11,
+    inverted: false,
+};
+
+/// Approximates rc-switch 'protocol3'. Note that proto3 uses asymmetric
+/// bit timings (`zero = {4, 11}`, `one = {9, 6}`) which this crate's
+/// symmetric `short`/`long` model cannot express: the "1" symbol is
+/// emitted as `{long, short} = {11, 4}` rather than `{9, 6}`, so this
+/// constant will not reliably drive genuine protocol-3 devices.
+pub const P3: ProtocolProperties = ProtocolProperties {
+    short: 4,
+    long: 11,
+    sync_bit: 30,
+    sync_gap: 71,
+    inverted: false,
+};
+
+/// Resembles rc-switch 'protocol4'.
+pub const P4: ProtocolProperties = ProtocolProperties {
+    short: 1,
+    long: 3,
+    sync_bit: 1,
+    sync_gap: 6,
+    inverted: false,
+};
+
+/// Resembles rc-switch 'protocol5'.
+pub const P5: ProtocolProperties = ProtocolProperties {
+    short: 1,
+    long: 2,
+    sync_bit: 6,
+    sync_gap: 14,
+    inverted: false,
+};
+
+/// Resembles rc-switch 'protocol6' (HT6P20B). Uses an inverted signal.
+pub const P6: ProtocolProperties = ProtocolProperties {
+    short: 1,
+    long: 2,
+    sync_bit: 23,
+    sync_gap: 1,
+    inverted: true,
+};
+
+/// Resembles rc-switch 'protocol7' (HS2303-PT).
+pub const P7: ProtocolProperties = ProtocolProperties {
+    short: 1,
+    long: 6,
+    sync_bit: 2,
+    sync_gap: 62,
+    inverted: false,
+};
+
+/// Resembles rc-switch 'protocol8' (Conrad RS-200 RX).
+pub const P8: ProtocolProperties = ProtocolProperties {
+    short: 7,
+    long: 16,
+    sync_bit: 3,
+    sync_gap: 130,
+    inverted: false,
+};
+
+/// Resembles rc-switch 'protocol9' (Conrad RS-200 TX). Uses an inverted signal.
+pub const P9: ProtocolProperties = ProtocolProperties {
+    short: 16,
+    long: 7,
+    sync_bit: 130,
+    sync_gap: 7,
+    inverted: true,
+};
+
+/// Resembles rc-switch 'protocol10' (1ByOne doorbell). Uses an inverted signal.
+pub const P10: ProtocolProperties = ProtocolProperties {
+    short: 3,
+    long: 1,
+    sync_bit: 18,
+    sync_gap: 1,
+    inverted: true,
+};
+
+/// Resembles rc-switch 'protocol11' (HT12E). Uses an inverted signal.
+pub const P11: ProtocolProperties = ProtocolProperties {
+    short: 1,
+    long: 2,
+    sync_bit: 36,
+    sync_gap: 1,
+    inverted: true,
+};
+
+/// Resembles rc-switch 'protocol12' (SM5212). Uses an inverted signal.
+pub const P12: ProtocolProperties = ProtocolProperties {
+    short: 1,
+    long: 2,
+    sync_bit: 36,
+    sync_gap: 1,
+    inverted: true,
 };
 
 impl Transmission {
@@ -99,7 +257,7 @@ impl Transmission {
     /// All other characters will result in a sync bit.
     ///
     /// # Examples
-    /// ```rust
+    /// ```no_run
     /// use libsparkypi::*;
     ///
     /// let my_signal = Transmission::builder()
@@ -112,30 +270,66 @@ impl Transmission {
     /// // output on device /dev/gpiochip0, gpio pin 18
     /// my_signal.send_to("/dev/gpiochip0", 18).unwrap();
     /// ```
+    #[cfg(feature = "gpio-cdev")]
     pub fn send_to(&self, gpio_dev: &str, gpio_pin: u8) -> Result<(), gpio_cdev::Error> {
-        
+
         let mut chip = Chip::new(gpio_dev)?;
 
         let lh = chip
             .get_line(gpio_pin as u32)?
             .request(LineRequestFlags::OUTPUT, 0, "tx")?;
 
+        let mut pin = CdevPin(lh);
+
+        // Measure the `set_value` write latency once so the busy-wait
+        // can subtract it from every delay and emit pulses of the
+        // intended width.
+        let compensation = calibrate(&mut pin).map_err(|e| e.0)?;
+        let mut delay = LinuxDelay { mode: self.timing, compensation };
+
+        // Best effort real-time priority around the transmit burst; the
+        // guard restores the previous scheduler when it drops at the end
+        // of the call.
+        let _guard = RealtimeGuard::acquire();
+
+        self.transmit(&mut pin, &mut delay).map_err(|e| e.0)
+    }
+
+    /// Transmit the signal through any [`embedded_hal::digital::OutputPin`],
+    /// driving the pulse timing with a [`embedded_hal::delay::DelayNs`].
+    ///
+    /// This is the backend-agnostic core that [`send_to`](Transmission::send_to)
+    /// wraps for the Linux gpio character device. Because it is generic
+    /// over the HAL traits, the same `Transmission` and
+    /// `ProtocolProperties` can drive a radio module bare-metal on a
+    /// microcontroller through any crate that exposes its pins as
+    /// `OutputPin` (e.g. `stm32f1xx-hal`), without going through the
+    /// [`csv_as_bytes`](Transmission::csv_as_bytes) UART offload.
+    ///
+    /// The sequence is transmitted exactly like `send_to`: a '1'
+    /// character is a binary one, a '0' a binary zero, and any other
+    /// character a sync bit.
+    pub fn transmit<P, D>(&self, pin: &mut P, delay: &mut D) -> Result<(), P::Error>
+    where
+        P: OutputPin,
+        D: DelayNs,
+    {
         for _ in 0..self.repeats {
-            
+
             for c in self.sequence.chars() {
-                
+
                 if c == '1' {
-                    send_bit(&lh, true, self.pulse_length, self.protocol.long, self.protocol.short)?;
+                    send_bit(pin, delay, true, self.pulse_length, self.protocol.long, self.protocol.short, self.protocol.inverted)?;
                 } else if c == '0' {
-                    send_bit(&lh, false, self.pulse_length, self.protocol.long, self.protocol.short)?;
+                    send_bit(pin, delay, false, self.pulse_length, self.protocol.long, self.protocol.short, self.protocol.inverted)?;
                 } else {
-                    send_sync_bit(&lh, self.pulse_length, self.protocol.sync_gap, self.protocol.sync_bit)?;
+                    send_sync_bit(pin, delay, self.pulse_length, self.protocol.sync_gap, self.protocol.sync_bit, self.protocol.inverted)?;
                 }
-            
+
             }
-        
+
         }
-    
+
         Ok(())
     }
 
@@ -186,28 +380,177 @@ impl Transmission {
 // Short pulse and long pause results in a binary zero.
 // The relation between short and long period is defined in the 'ProtocolProperties' struct.
 
-fn send_bit(lh: &LineHandle, bit: bool, pulse_length: u16, factor1: u8, factor2: u8) -> Result<(), gpio_cdev::Error> {
-    if bit {
-        lh.set_value(1)?;
-        thread::sleep(time::Duration::from_micros(pulse_length as u64 * factor1 as u64));
-        lh.set_value(0)?;
-        thread::sleep(time::Duration::from_micros(pulse_length as u64 * factor2 as u64));
+// Drive the pin for a single pulse/gap period. When 'inverted' is set
+// the line is held low during the pulse and high during the gap, as
+// required by protocols like the HT6P20B.
+
+fn drive<P, D>(pin: &mut P, delay: &mut D, inverted: bool, pulse_factor: u8, gap_factor: u8, pulse_length: u16) -> Result<(), P::Error>
+where
+    P: OutputPin,
+    D: DelayNs,
+{
+    if inverted {
+        pin.set_low()?;
     } else {
-        lh.set_value(1)?;
-        thread::sleep(time::Duration::from_micros(pulse_length as u64 * factor2 as u64));
-        lh.set_value(0)?;
-        thread::sleep(time::Duration::from_micros(pulse_length as u64 * factor1 as u64));
+        pin.set_high()?;
     }
+    delay.delay_us(pulse_length as u32 * pulse_factor as u32);
+    if inverted {
+        pin.set_high()?;
+    } else {
+        pin.set_low()?;
+    }
+    delay.delay_us(pulse_length as u32 * gap_factor as u32);
     Ok(())
 }
 
+// Send one single bit.
+// Long pulse and short pause results in a binary one.
+// Short pulse and long pause results in a binary zero.
+// The relation between short and long period is defined in the 'ProtocolProperties' struct.
+
+fn send_bit<P, D>(pin: &mut P, delay: &mut D, bit: bool, pulse_length: u16, factor1: u8, factor2: u8, inverted: bool) -> Result<(), P::Error>
+where
+    P: OutputPin,
+    D: DelayNs,
+{
+    if bit {
+        drive(pin, delay, inverted, factor1, factor2, pulse_length)
+    } else {
+        drive(pin, delay, inverted, factor2, factor1, pulse_length)
+    }
+}
+
 // A so called sync bit must be transmitted before the actual binary sequence.
 // The relation between 'high' and 'low' status is defined in the 'ProtocolProperties' struct.
 
-fn send_sync_bit(lh: &LineHandle, pulse_length: u16, factor1: u8, factor2: u8) -> Result<(), gpio_cdev::Error> {
-    lh.set_value(1)?;
-    thread::sleep(time::Duration::from_micros(pulse_length as u64 * factor2 as u64));
-    lh.set_value(0)?;
-    thread::sleep(time::Duration::from_micros(pulse_length as u64 * factor1 as u64));
-    Ok(())
+fn send_sync_bit<P, D>(pin: &mut P, delay: &mut D, pulse_length: u16, factor1: u8, factor2: u8, inverted: bool) -> Result<(), P::Error>
+where
+    P: OutputPin,
+    D: DelayNs,
+{
+    drive(pin, delay, inverted, factor2, factor1, pulse_length)
+}
+
+/// Wraps a [`gpio_cdev::LineHandle`] as an [`embedded_hal::digital::OutputPin`]
+/// so the Linux backend can share the generic transmit core.
+#[cfg(feature = "gpio-cdev")]
+struct CdevPin(LineHandle);
+
+/// `embedded-hal` error wrapper around a [`gpio_cdev::Error`].
+#[cfg(feature = "gpio-cdev")]
+#[derive(Debug)]
+struct CdevError(gpio_cdev::Error);
+
+#[cfg(feature = "gpio-cdev")]
+impl embedded_hal::digital::Error for CdevError {
+    fn kind(&self) -> embedded_hal::digital::ErrorKind {
+        embedded_hal::digital::ErrorKind::Other
+    }
+}
+
+#[cfg(feature = "gpio-cdev")]
+impl embedded_hal::digital::ErrorType for CdevPin {
+    type Error = CdevError;
+}
+
+#[cfg(feature = "gpio-cdev")]
+impl OutputPin for CdevPin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.0.set_value(0).map_err(CdevError)
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.0.set_value(1).map_err(CdevError)
+    }
+}
+
+/// A [`DelayNs`] for the Linux path that honours the transmission's
+/// [`TimingMode`] and subtracts the calibrated gpio write latency from
+/// every delay.
+#[cfg(feature = "gpio-cdev")]
+struct LinuxDelay {
+    mode: TimingMode,
+    compensation: time::Duration,
+}
+
+#[cfg(feature = "gpio-cdev")]
+impl DelayNs for LinuxDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        let target = time::Duration::from_nanos(ns as u64).saturating_sub(self.compensation);
+        match self.mode {
+            TimingMode::Sleep => thread::sleep(target),
+            TimingMode::BusyWait => {
+                let start = time::Instant::now();
+                while start.elapsed() < target {
+                    std::hint::spin_loop();
+                }
+            }
+        }
+    }
+}
+
+// Measure the average duration of a single `set_value` call by toggling
+// the line a number of times. Used to compensate the emitted pulse
+// widths for the syscall overhead.
+#[cfg(feature = "gpio-cdev")]
+fn calibrate(pin: &mut CdevPin) -> Result<time::Duration, CdevError> {
+    const ROUNDS: u32 = 100;
+    let start = time::Instant::now();
+    for _ in 0..ROUNDS {
+        pin.set_high()?;
+        pin.set_low()?;
+    }
+    Ok(start.elapsed() / (ROUNDS * 2))
+}
+
+/// Best-effort `SCHED_FIFO` real-time priority for the transmit burst.
+///
+/// With the `realtime` feature enabled the calling thread is switched to
+/// `SCHED_FIFO` while the guard is alive and restored when it is
+/// dropped; without the feature it is a no-op. Failures (e.g. missing
+/// `CAP_SYS_NICE`) are ignored - the transmission still runs, just
+/// without the scheduling guarantee.
+#[cfg(feature = "gpio-cdev")]
+struct RealtimeGuard {
+    #[cfg(feature = "realtime")]
+    previous: Option<(libc::c_int, libc::sched_param)>,
+}
+
+#[cfg(feature = "gpio-cdev")]
+impl RealtimeGuard {
+    #[cfg(feature = "realtime")]
+    fn acquire() -> Self {
+        // Safety: the libc scheduler calls operate on the current
+        // thread (pid 0) and a locally owned `sched_param`.
+        unsafe {
+            let mut previous = libc::sched_param { sched_priority: 0 };
+            let policy = libc::sched_getscheduler(0);
+            let saved = if libc::sched_getparam(0, &mut previous) == 0 && policy >= 0 {
+                Some((policy, previous))
+            } else {
+                None
+            };
+            let param = libc::sched_param { sched_priority: 1 };
+            libc::sched_setscheduler(0, libc::SCHED_FIFO, &param);
+            RealtimeGuard { previous: saved }
+        }
+    }
+
+    #[cfg(not(feature = "realtime"))]
+    fn acquire() -> Self {
+        RealtimeGuard {}
+    }
+}
+
+#[cfg(all(feature = "gpio-cdev", feature = "realtime"))]
+impl Drop for RealtimeGuard {
+    fn drop(&mut self) {
+        if let Some((policy, param)) = self.previous {
+            // Safety: restores the scheduler of the current thread.
+            unsafe {
+                libc::sched_setscheduler(0, policy, &param);
+            }
+        }
+    }
 }
@@ -0,0 +1,165 @@
+//! Send arbitrary byte payloads over an FS1000A transmitter, the way
+//! RadioHead's `RH_ASK` driver does, turning the crate into a simple
+//! one-way telemetry link instead of just a remote-control emitter.
+//!
+//! A packet is framed as a training preamble of alternating `"10"`
+//! symbols (to settle the receiver's AGC), a sync start marker, a length
+//! byte, the payload, and a trailing CRC-16 (CCITT, polynomial `0x1021`,
+//! initial value `0xFFFF`) computed over the length byte plus the
+//! payload. Each byte is expanded most-significant-bit first into the
+//! on/off symbols consumed by the transmitter.
+//!
+//! [`encode`] returns a [`String`] ready to drop into
+//! [`TransmissionBuilder::sequence`](crate::builder::TransmissionBuilder::sequence);
+//! [`decode`] is its inverse and pairs with the
+//! [`receiver`](crate::receiver) subsystem.
+
+use std::error::Error;
+use std::fmt;
+
+/// Default length of the training preamble, in bits.
+pub const PREAMBLE_BITS: usize = 36;
+
+/// Errors that can occur while decoding a packet.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PacketError {
+    /// No sync start marker was found in the sequence.
+    NoSync,
+    /// The sequence ended before a full frame could be read.
+    Truncated,
+    /// The trailing CRC-16 did not match the payload.
+    Crc,
+}
+
+impl fmt::Display for PacketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PacketError::NoSync => write!(f, "no sync marker found"),
+            PacketError::Truncated => write!(f, "sequence truncated before end of frame"),
+            PacketError::Crc => write!(f, "crc mismatch"),
+        }
+    }
+}
+
+impl Error for PacketError {}
+
+/// Compute the CRC-16/CCITT-FALSE checksum (polynomial `0x1021`, initial
+/// value `0xFFFF`, no reflection) over `data`.
+pub fn crc16_ccitt(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            if crc & 0x8000 != 0 {
+                crc = (crc << 1) ^ 0x1021;
+            } else {
+                crc <<= 1;
+            }
+        }
+    }
+    crc
+}
+
+// Append the eight bits of `byte`, most significant first.
+fn push_byte(sequence: &mut String, byte: u8) {
+    for i in (0..8).rev() {
+        sequence.push(if byte >> i & 1 == 1 { '1' } else { '0' });
+    }
+}
+
+/// Encode `payload` into a packet sequence: preamble, sync start marker,
+/// length byte, payload and CRC-16.
+///
+/// The resulting string can be handed straight to a builder.
+///
+/// # Examples
+/// ```rust
+/// use libsparkypi::*;
+///
+/// let signal = Transmission::builder()
+///     .sequence(&packet::encode(b"hi"))
+///     .pulse_length(320)
+///     .repeats(5)
+///     .protocol(P1)
+///     .build();
+/// ```
+pub fn encode(payload: &[u8]) -> String {
+    let length = payload.len() as u8;
+
+    let mut checked = Vec::with_capacity(payload.len() + 1);
+    checked.push(length);
+    checked.extend_from_slice(payload);
+    let crc = crc16_ccitt(&checked);
+
+    let mut sequence = String::with_capacity(PREAMBLE_BITS + 1 + (checked.len() + 2) * 8);
+
+    // Training preamble of alternating "10" symbols.
+    for _ in 0..PREAMBLE_BITS / 2 {
+        sequence.push_str("10");
+    }
+    // Sync start marker (any non-'0'/'1' character is a sync bit).
+    sequence.push('s');
+
+    push_byte(&mut sequence, length);
+    for &byte in payload {
+        push_byte(&mut sequence, byte);
+    }
+    push_byte(&mut sequence, (crc >> 8) as u8);
+    push_byte(&mut sequence, crc as u8);
+
+    sequence
+}
+
+// Read eight bits from the iterator, most significant first.
+fn take_byte<I: Iterator<Item = u8>>(bits: &mut I) -> Option<u8> {
+    let mut byte = 0u8;
+    for _ in 0..8 {
+        byte = (byte << 1) | bits.next()?;
+    }
+    Some(byte)
+}
+
+/// Decode a packet sequence produced by [`encode`] (or recovered by the
+/// [`receiver`](crate::receiver) subsystem) back into its payload.
+///
+/// The preamble is skipped, the sync start marker located, the length
+/// read, the payload collected and the trailing CRC-16 validated before
+/// the payload is returned.
+///
+/// # Examples
+/// ```rust
+/// use libsparkypi::packet;
+///
+/// let sequence = packet::encode(b"hi");
+/// assert_eq!(packet::decode(&sequence).unwrap(), b"hi");
+/// ```
+pub fn decode(sequence: &str) -> Result<Vec<u8>, PacketError> {
+    // Everything up to and including the sync marker is the preamble.
+    let data = sequence
+        .split(|c| c != '0' && c != '1')
+        .nth(1)
+        .ok_or(PacketError::NoSync)?;
+    if data.is_empty() {
+        return Err(PacketError::NoSync);
+    }
+
+    let mut bits = data.chars().map(|c| (c == '1') as u8);
+
+    let length = take_byte(&mut bits).ok_or(PacketError::Truncated)?;
+
+    let mut checked = Vec::with_capacity(length as usize + 1);
+    checked.push(length);
+    for _ in 0..length {
+        checked.push(take_byte(&mut bits).ok_or(PacketError::Truncated)?);
+    }
+
+    let high = take_byte(&mut bits).ok_or(PacketError::Truncated)?;
+    let low = take_byte(&mut bits).ok_or(PacketError::Truncated)?;
+    let crc = (high as u16) << 8 | low as u16;
+
+    if crc != crc16_ccitt(&checked) {
+        return Err(PacketError::Crc);
+    }
+
+    Ok(checked[1..].to_vec())
+}
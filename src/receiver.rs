@@ -0,0 +1,338 @@
+//! Sniff and decode incoming 433MHz OOK frames — the inverse of
+//! [`Transmission::send_to`](crate::Transmission::send_to).
+//!
+//! A [`Receiver`] requests a gpio line as an input and watches it for
+//! rising and falling edges. Every edge is timestamped, the duration of
+//! the preceding pulse is computed in microseconds, and the resulting
+//! train of pulses is matched against a protocol. This is the equivalent
+//! of the `RFSniffer` utility from 433Utils or the receive half of
+//! sui77's [rc-switch library](https://github.com/sui77/rc-switch) and
+//! makes the crate usable for learning the codes of unknown remotes.
+
+use gpio_cdev::{Chip, LineRequestFlags, EventRequestFlags};
+use crate::{Transmission, ProtocolProperties, P1, P2, XEN};
+
+/// The protocols a [`Receiver`] tries, in order, when no explicit
+/// protocol is given. The first one that decodes cleanly wins.
+pub const REGISTRY: &[ProtocolProperties] = &[P1, P2, XEN];
+
+/// The result of successfully decoding a frame.
+///
+/// The `sequence` is composed the same way it is consumed by
+/// [`Transmission::send_to`](crate::Transmission::send_to): a leading
+/// sync marker followed by literal '0' and '1' characters, so it can be
+/// dropped straight back into a [`TransmissionBuilder`](crate::builder::TransmissionBuilder).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decoded {
+    pub sequence: String,
+    pub pulse_length: u16,
+    pub protocol: ProtocolProperties,
+}
+
+impl Decoded {
+    /// Rebuild a [`Transmission`] from the decoded frame, so a learnt
+    /// code can be replayed verbatim. The number of repeats is left at
+    /// its default and should be set by the caller before transmitting.
+    pub fn to_transmission(&self) -> Transmission {
+        Transmission {
+            sequence: self.sequence.clone(),
+            pulse_length: self.pulse_length,
+            repeats: 0,
+            protocol: self.protocol,
+            timing: Default::default(),
+        }
+    }
+}
+
+/// Sniffs gpio edges and decodes OOK frames back into a [`Decoded`]
+/// sequence.
+///
+/// # Examples
+/// ```no_run
+/// use libsparkypi::receiver::Receiver;
+///
+/// let rx = Receiver::new()
+///     .min_repeats(2);
+///
+/// // listen on device /dev/gpiochip0, gpio pin 27
+/// let decoded = rx.listen("/dev/gpiochip0", 27).unwrap();
+/// println!("{} @ {}us", decoded.sequence, decoded.pulse_length);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Receiver {
+    tolerance: f64,
+    min_repeats: u8,
+    edges: usize,
+}
+
+impl Default for Receiver {
+    fn default() -> Self {
+        Receiver {
+            tolerance: 0.6,
+            min_repeats: 2,
+            edges: 4096,
+        }
+    }
+}
+
+impl Receiver {
+    /// Creates a receiver with sensible defaults: a timing tolerance of
+    /// ±60%, two required repeats and room for 4096 captured edges.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The relative tolerance used when matching a measured pulse
+    /// against its expected length, e.g. `0.6` for ±60%. Real receivers
+    /// are noisy, so a generous tolerance is usually required.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// The number of identical frames that must be observed in a row
+    /// before a decode is accepted. Remotes send the same code several
+    /// times, so requiring repeats rejects spurious noise.
+    pub fn min_repeats(mut self, min_repeats: u8) -> Self {
+        self.min_repeats = min_repeats;
+        self
+    }
+
+    /// The maximum number of edges to capture in a single listen call
+    /// before giving up.
+    pub fn edges(mut self, edges: usize) -> Self {
+        self.edges = edges;
+        self
+    }
+
+    /// Request `gpio_pin` on `gpio_dev` as an input and block until a
+    /// frame has been decoded cleanly by one of the protocols in
+    /// [`REGISTRY`] at least [`min_repeats`](Receiver::min_repeats)
+    /// times in a row.
+    ///
+    /// The device naming follows the same rules as
+    /// [`Transmission::send_to`](crate::Transmission::send_to).
+    pub fn listen(&self, gpio_dev: &str, gpio_pin: u8) -> Result<Decoded, gpio_cdev::Error> {
+        loop {
+            let pulses = self.capture(gpio_dev, gpio_pin)?;
+            if let Some(decoded) = self.decode(&pulses) {
+                return Ok(decoded);
+            }
+            // Nothing clean in this capture - keep sniffing.
+        }
+    }
+
+    /// Capture a train of pulse durations (in microseconds) from the
+    /// gpio line. Each element is the duration the line held a level
+    /// before the edge that ended it; the accompanying boolean is the
+    /// level itself (`true` for high).
+    fn capture(&self, gpio_dev: &str, gpio_pin: u8) -> Result<Vec<(bool, u64)>, gpio_cdev::Error> {
+        let mut chip = Chip::new(gpio_dev)?;
+        let line = chip.get_line(gpio_pin as u32)?;
+        let events = line.events(
+            LineRequestFlags::INPUT,
+            EventRequestFlags::BOTH_EDGES,
+            "rx",
+        )?;
+
+        let mut pulses = Vec::with_capacity(self.edges);
+        let mut last: Option<u64> = None;
+
+        for event in events {
+            let event = event?;
+            let now = event.timestamp();
+            if let Some(prev) = last {
+                // A rising edge ends a low level, a falling edge ends a
+                // high level.
+                let high = event.event_type() == gpio_cdev::EventType::FallingEdge;
+                pulses.push((high, (now - prev) / 1_000));
+            }
+            last = Some(now);
+            if pulses.len() >= self.edges {
+                break;
+            }
+        }
+
+        Ok(pulses)
+    }
+
+    /// Try every protocol in [`REGISTRY`] and return the first frame
+    /// that decodes cleanly.
+    pub fn decode(&self, pulses: &[(bool, u64)]) -> Option<Decoded> {
+        REGISTRY.iter().find_map(|p| self.decode_with(*p, pulses))
+    }
+
+    /// Attempt to decode `pulses` with a single, explicit protocol.
+    ///
+    /// The stream is split into frames on the long low sync gap, each
+    /// frame is decoded into a '0'/'1' sequence, and a decode is only
+    /// returned once [`min_repeats`](Receiver::min_repeats) identical
+    /// frames have been seen.
+    pub fn decode_with(&self, protocol: ProtocolProperties, pulses: &[(bool, u64)]) -> Option<Decoded> {
+        if protocol.sync_gap == 0 {
+            return None;
+        }
+
+        let mut frames: Vec<(String, u16)> = Vec::new();
+        let mut i = 0;
+
+        while i < pulses.len() {
+            // A frame begins after a long low gap of roughly
+            // `sync_gap * pulse_length` microseconds. The pulse length
+            // is inferred from the measured gap.
+            let (level, micros) = pulses[i];
+            i += 1;
+            if level {
+                continue;
+            }
+            let pulse_length = micros as f64 / protocol.sync_gap as f64;
+            if pulse_length < 1.0 {
+                continue;
+            }
+
+            if let Some(frame) = self.decode_frame(protocol, pulse_length, pulses, &mut i) {
+                let pl = pulse_length.round() as u16;
+                frames.push((frame, pl));
+            }
+        }
+
+        // Require `min_repeats` identical frames in a row.
+        let mut run = 0;
+        for (k, (frame, pl)) in frames.iter().enumerate() {
+            if k > 0 && frames[k - 1].0 == *frame {
+                run += 1;
+            } else {
+                run = 1;
+            }
+            if run >= self.min_repeats as usize {
+                return Some(Decoded {
+                    sequence: format!("s{}", frame),
+                    pulse_length: *pl,
+                    protocol,
+                });
+            }
+        }
+
+        None
+    }
+
+    /// Decode the bit pairs of a single frame starting at `*cursor`,
+    /// advancing the cursor past the frame. Returns `None` if any pulse
+    /// fails to classify within tolerance.
+    fn decode_frame(
+        &self,
+        protocol: ProtocolProperties,
+        pulse_length: f64,
+        pulses: &[(bool, u64)],
+        cursor: &mut usize,
+    ) -> Option<String> {
+        let short = pulse_length * protocol.short as f64;
+        let long = pulse_length * protocol.long as f64;
+        let sync_gap = pulse_length * protocol.sync_gap as f64;
+
+        let mut sequence = String::new();
+
+        while *cursor + 1 < pulses.len() {
+            let (high_level, high) = pulses[*cursor];
+            let (low_level, low) = pulses[*cursor + 1];
+
+            // The next sync gap ends the frame.
+            if self.matches(low as f64, sync_gap) {
+                break;
+            }
+            // We expect a high pulse followed by a low pause.
+            if !high_level || low_level {
+                return None;
+            }
+
+            if self.matches(high as f64, short) && self.matches(low as f64, long) {
+                sequence.push('0');
+            } else if self.matches(high as f64, long) && self.matches(low as f64, short) {
+                sequence.push('1');
+            } else {
+                return None;
+            }
+
+            *cursor += 2;
+        }
+
+        if sequence.is_empty() {
+            None
+        } else {
+            Some(sequence)
+        }
+    }
+
+    /// Whether `measured` lies within the configured tolerance of
+    /// `expected`.
+    fn matches(&self, measured: f64, expected: f64) -> bool {
+        (measured - expected).abs() <= expected * self.tolerance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::P1;
+
+    // Build a pulse train for a bit string, the inverse of what
+    // `send_to` would emit: a leading sync (high sync_bit, low sync_gap)
+    // followed by a high pulse and low pause per bit, with a trailing
+    // sync to terminate the last frame.
+    fn frame(bits: &str, p: ProtocolProperties, pl: u64) -> Vec<(bool, u64)> {
+        let mut pulses = vec![(true, p.sync_bit as u64 * pl), (false, p.sync_gap as u64 * pl)];
+        for c in bits.chars() {
+            if c == '1' {
+                pulses.push((true, p.long as u64 * pl));
+                pulses.push((false, p.short as u64 * pl));
+            } else {
+                pulses.push((true, p.short as u64 * pl));
+                pulses.push((false, p.long as u64 * pl));
+            }
+        }
+        pulses
+    }
+
+    fn train(bits: &str, repeats: usize, p: ProtocolProperties, pl: u64) -> Vec<(bool, u64)> {
+        let mut pulses = Vec::new();
+        for _ in 0..repeats {
+            pulses.extend(frame(bits, p, pl));
+        }
+        // Trailing sync terminates the final frame.
+        pulses.push((true, p.sync_bit as u64 * pl));
+        pulses.push((false, p.sync_gap as u64 * pl));
+        pulses
+    }
+
+    #[test]
+    fn decodes_a_clean_frame() {
+        let pulses = train("0110", 3, P1, 300);
+        let decoded = Receiver::new().decode_with(P1, &pulses).unwrap();
+        assert_eq!(decoded.sequence, "s0110");
+        assert_eq!(decoded.pulse_length, 300);
+        assert_eq!(decoded.protocol, P1);
+    }
+
+    #[test]
+    fn registry_decode_picks_p1() {
+        let pulses = train("0011", 2, P1, 300);
+        let decoded = Receiver::new().decode(&pulses).unwrap();
+        assert_eq!(decoded.sequence, "s0011");
+        assert_eq!(decoded.protocol, P1);
+    }
+
+    #[test]
+    fn rejects_fewer_than_min_repeats() {
+        // A single frame does not meet the default two-repeat threshold.
+        let pulses = train("0110", 1, P1, 300);
+        assert!(Receiver::new().decode_with(P1, &pulses).is_none());
+    }
+
+    #[test]
+    fn honours_relaxed_min_repeats() {
+        // A single frame is enough once the threshold is lowered to one.
+        let pulses = train("0110", 1, P1, 300);
+        let rx = Receiver::new().min_repeats(1);
+        assert!(rx.decode_with(P1, &pulses).is_some());
+    }
+}